@@ -1,6 +1,13 @@
+use std::fmt::Write as _;
+
 use log::{debug, trace};
-use rustc_middle::mir::{visit::Visitor, *};
-use rustc_mir_dataflow::ResultsVisitor;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::{
+  mir::{visit::Visitor, *},
+  ty::ScalarInt,
+};
+use rustc_index::IndexVec;
+use rustc_mir_dataflow::{Analysis, AnalysisDomain, Backward, JoinSemiLattice, ResultsVisitor};
 use rustc_span::Span;
 
 use super::{
@@ -25,11 +32,488 @@ pub enum Direction {
   Both,
 }
 
+/// Tuning knobs for [`compute_dependencies`]'s opt-in precision passes. Kept
+/// as a single struct now that there's more than one independent flag, so
+/// call sites don't accumulate positional `bool`s.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SliceOptions {
+  /// Run [`ConstPropAnalysis`] first and drop locations that are only
+  /// reachable through a `SwitchInt` edge it proves dead.
+  pub prune_dead_branches: bool,
+  /// For `Direction::Backward` (and the backward half of `Both`), drop
+  /// places/locations whose assignment is dead -- i.e. not live -- at the
+  /// point it reaches the queried target. Forward slices are unaffected.
+  pub minimal_backward_slice: bool,
+  /// Run [`PointsToVisitor`] first and narrow `aliases.conflicts(mutated)`
+  /// for a write through a pointer down to what it flow-sensitively proves
+  /// `mutated` can reach (see [`DepVisitor::refine_conflicts`]).
+  pub flow_sensitive_aliasing: bool,
+}
+
+/// A flat lattice tracking whether a place's value is statically known, used
+/// by [`ConstPropAnalysis`] to prune `SwitchInt` control dependencies whose
+/// outcome is determined at compile time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ConstLattice {
+  Bottom,
+  Const(ScalarInt),
+  Top,
+}
+
+impl ConstLattice {
+  fn join(self, other: Self) -> Self {
+    match (self, other) {
+      (Self::Bottom, x) | (x, Self::Bottom) => x,
+      (Self::Const(a), Self::Const(b)) if a == b => Self::Const(a),
+      _ => Self::Top,
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct ConstPropDomain(FxHashMap<Local, ConstLattice>);
+
+impl ConstPropDomain {
+  fn get(&self, local: Local) -> ConstLattice {
+    self.0.get(&local).copied().unwrap_or(ConstLattice::Bottom)
+  }
+
+  fn set(&mut self, local: Local, value: ConstLattice) {
+    self.0.insert(local, value);
+  }
+}
+
+impl JoinSemiLattice for ConstPropDomain {
+  fn join(&mut self, other: &Self) -> bool {
+    let mut changed = false;
+    for (&local, &value) in &other.0 {
+      let joined = self.get(local).join(value);
+      if joined != self.get(local) {
+        self.set(local, joined);
+        changed = true;
+      }
+    }
+    changed
+  }
+}
+
+/// A lightweight forward constant-propagation analysis used to determine,
+/// for a `SwitchInt` whose discriminant is a compile-time constant, which
+/// successor block is actually live. This lets [`DepVisitor`] skip inserting
+/// control dependencies that only a dead edge would require.
+struct ConstPropAnalysis;
+
+impl<'tcx> AnalysisDomain<'tcx> for ConstPropAnalysis {
+  type Domain = ConstPropDomain;
+  const NAME: &'static str = "FlowistryConstProp";
+
+  fn bottom_value(&self, _body: &Body<'tcx>) -> Self::Domain {
+    ConstPropDomain::default()
+  }
+
+  fn initialize_start_block(&self, _body: &Body<'tcx>, _state: &mut Self::Domain) {}
+}
+
+impl<'tcx> Analysis<'tcx> for ConstPropAnalysis {
+  fn apply_statement_effect(
+    &self,
+    state: &mut Self::Domain,
+    statement: &Statement<'tcx>,
+    _location: Location,
+  ) {
+    let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+      return;
+    };
+    if !place.projection.is_empty() {
+      return;
+    }
+
+    let value = match rvalue {
+      Rvalue::Use(Operand::Constant(box constant)) => constant
+        .literal
+        .try_to_scalar_int()
+        .map_or(ConstLattice::Top, ConstLattice::Const),
+      Rvalue::Use(Operand::Copy(p) | Operand::Move(p)) if p.projection.is_empty() => {
+        state.get(p.local)
+      }
+      _ => ConstLattice::Top,
+    };
+    state.set(place.local, value);
+  }
+
+  fn apply_terminator_effect(
+    &self,
+    state: &mut Self::Domain,
+    terminator: &Terminator<'tcx>,
+    _location: Location,
+  ) {
+    // Terminators can write a place too (a call's destination, a
+    // generator's resume argument, ...); these are never statically known,
+    // so the written place must be killed to `Top` just like an opaque
+    // statement assignment would be. Otherwise a prior `Const` for that
+    // local leaks past the terminator and corrupts a later `SwitchInt`.
+    let written = match &terminator.kind {
+      TerminatorKind::Call { destination, .. } => Some(*destination),
+      TerminatorKind::Yield { resume_arg, .. } => Some(*resume_arg),
+      _ => None,
+    };
+    if let Some(place) = written {
+      if place.projection.is_empty() {
+        state.set(place.local, ConstLattice::Top);
+      }
+    }
+  }
+}
+
+/// Runs [`ConstPropAnalysis`] and collects, for every `SwitchInt` with a
+/// resolved constant discriminant, the set of `(block, successor)` edges
+/// that are statically dead (the discriminant provably never takes them).
+fn dead_switch_edges(
+  tcx: rustc_middle::ty::TyCtxt<'tcx>,
+  body: &'mir Body<'tcx>,
+) -> FxHashSet<(BasicBlock, BasicBlock)> {
+  let mut cursor = ConstPropAnalysis
+    .into_engine(tcx, body)
+    .iterate_to_fixpoint()
+    .into_results_cursor(body);
+
+  let mut dead_edges = FxHashSet::default();
+  for (block, data) in body.basic_blocks().iter_enumerated() {
+    let TerminatorKind::SwitchInt { discr, targets } = &data.terminator().kind else {
+      continue;
+    };
+    let Some(place) = discr.place() else { continue };
+    if !place.projection.is_empty() {
+      continue;
+    }
+
+    cursor.seek_before_primary_effect(body.terminator_loc(block));
+    let ConstLattice::Const(scalar) = cursor.get().get(place.local) else {
+      continue;
+    };
+    let Ok(bits) = scalar.try_to_bits(scalar.size()) else {
+      continue;
+    };
+
+    let live_target = targets
+      .iter()
+      .find_map(|(value, bb)| (value == bits).then_some(bb))
+      .unwrap_or_else(|| targets.otherwise());
+
+    dead_edges.extend(
+      targets
+        .all_targets()
+        .iter()
+        .copied()
+        .filter(|bb| *bb != live_target)
+        .map(|dead_successor| (block, dead_successor)),
+    );
+  }
+  dead_edges
+}
+
+/// A block is dead for slicing purposes only if it is unreachable from
+/// `START_BLOCK` once every edge in `dead_edges` is removed from the CFG --
+/// *not* simply everything transitively reachable from a dead successor,
+/// since a dead branch's successors (e.g. the join block after an
+/// `if <const> {...}`) are almost always also reachable via the live edge.
+fn dead_switch_blocks(
+  body: &'mir Body<'tcx>,
+  dead_edges: &FxHashSet<(BasicBlock, BasicBlock)>,
+) -> FxHashSet<BasicBlock> {
+  let mut reachable = FxHashSet::default();
+  let mut worklist = vec![START_BLOCK];
+  while let Some(block) = worklist.pop() {
+    if !reachable.insert(block) {
+      continue;
+    }
+    for successor in body.basic_blocks()[block].terminator().successors() {
+      if !dead_edges.contains(&(block, successor)) {
+        worklist.push(successor);
+      }
+    }
+  }
+
+  body
+    .basic_blocks()
+    .indices()
+    .filter(|block| !reachable.contains(block))
+    .collect()
+}
+
+/// Flow-sensitive points-to state: for every reference-typed local, the set
+/// of places it may currently point to.
+#[derive(Clone, Default, Debug)]
+struct PointsToState<'tcx> {
+  targets: FxHashMap<Local, FxHashSet<Place<'tcx>>>,
+}
+
+impl<'tcx> PointsToState<'tcx> {
+  fn targets_of(&self, place: Place<'tcx>) -> FxHashSet<Place<'tcx>> {
+    self.targets.get(&place.local).cloned().unwrap_or_default()
+  }
+
+  fn join(&mut self, other: &Self) -> bool {
+    let mut changed = false;
+    for (&local, other_targets) in &other.targets {
+      let entry = self.targets.entry(local).or_default();
+      for &target in other_targets {
+        changed |= entry.insert(target);
+      }
+    }
+    changed
+  }
+}
+
+/// Computes, at every [`Location`], the flow-sensitive set of places each
+/// reference-typed local may point to: `x = &y` sets `targets(x) = {y}`,
+/// `x = copy/move p` sets `targets(x) = targets(p)` (propagating reborrows
+/// transitively), and joins at merge points take the union.
+///
+/// A local's target set is replaced by each direct assignment to it and
+/// cleared when it is a `Call`/`Yield` destination, so it can grow or shrink
+/// along a path. The analysis is driven by [`PointsToVisitor::run`], which
+/// takes `self` by value, sweeps every block in order and repeats until no
+/// block's entry state changes; it does not go through
+/// `rustc_mir_dataflow`'s `&self` [`Analysis`] trait.
+struct PointsToVisitor<'a, 'tcx> {
+  body: &'a Body<'tcx>,
+  block_entry: IndexVec<BasicBlock, PointsToState<'tcx>>,
+  location_state: FxHashMap<Location, PointsToState<'tcx>>,
+}
+
+impl<'a, 'tcx> PointsToVisitor<'a, 'tcx> {
+  fn new(body: &'a Body<'tcx>) -> Self {
+    PointsToVisitor {
+      body,
+      block_entry: IndexVec::from_elem_n(PointsToState::default(), body.basic_blocks().len()),
+      location_state: FxHashMap::default(),
+    }
+  }
+
+  fn run(mut self) -> FxHashMap<Location, PointsToState<'tcx>> {
+    loop {
+      let mut changed = false;
+      for (block, data) in self.body.basic_blocks().iter_enumerated() {
+        let mut state = self.block_entry[block].clone();
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+          self.apply_statement(&mut state, statement);
+          self
+            .location_state
+            .insert(Location { block, statement_index }, state.clone());
+        }
+
+        self.apply_terminator(&mut state, data.terminator());
+        self
+          .location_state
+          .insert(self.body.terminator_loc(block), state.clone());
+
+        for successor in data.terminator().successors() {
+          let mut next = self.block_entry[successor].clone();
+          if next.join(&state) {
+            self.block_entry[successor] = next;
+            changed = true;
+          }
+        }
+      }
+
+      if !changed {
+        break;
+      }
+    }
+
+    self.location_state
+  }
+
+  fn apply_statement(&self, state: &mut PointsToState<'tcx>, statement: &Statement<'tcx>) {
+    let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+      return;
+    };
+    if !place.projection.is_empty() {
+      return;
+    }
+
+    let targets = match rvalue {
+      Rvalue::Ref(_, _, borrowed) | Rvalue::AddressOf(_, borrowed) => {
+        if matches!(borrowed.projection.last(), Some(ProjectionElem::Deref)) {
+          // A reborrow (`x = &*p` / `x = &mut *p`): `x` points to whatever
+          // `p` points to, not to the place `*p` itself. Propagate through
+          // the existing points-to facts for `p` transitively rather than
+          // recording the dereferenced place as the target.
+          state.targets_of(Place::from(borrowed.local))
+        } else {
+          std::iter::once(*borrowed).collect()
+        }
+      }
+      Rvalue::Use(Operand::Copy(p) | Operand::Move(p)) => state.targets_of(*p),
+      _ => FxHashSet::default(),
+    };
+    state.targets.insert(place.local, targets);
+  }
+
+  /// Kills the destination of a `Call`/`Yield` terminator: its provenance
+  /// after the call is unknown to this analysis, so any stale points-to
+  /// facts for it must be cleared rather than left to dangle.
+  fn apply_terminator(&self, state: &mut PointsToState<'tcx>, terminator: &Terminator<'tcx>) {
+    let destination = match &terminator.kind {
+      TerminatorKind::Call { destination, .. } => Some(*destination),
+      TerminatorKind::Yield { resume_arg, .. } => Some(*resume_arg),
+      _ => None,
+    };
+    if let Some(place) = destination {
+      if place.projection.is_empty() {
+        state.targets.remove(&place.local);
+      }
+    }
+  }
+}
+
+/// Runs [`PointsToVisitor`] to a fixpoint over `body`.
+fn compute_points_to(body: &'mir Body<'tcx>) -> FxHashMap<Location, PointsToState<'tcx>> {
+  PointsToVisitor::new(body).run()
+}
+
+/// Backward live-variables state: the set of places whose current value may
+/// still be read before being overwritten.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct LivenessDomain<'tcx>(PlaceSet<'tcx>);
+
+impl JoinSemiLattice for LivenessDomain<'tcx> {
+  fn join(&mut self, other: &Self) -> bool {
+    self.0.union(&other.0)
+  }
+}
+
+/// Collects the places read by a statement/terminator (the `gen` set of the
+/// standard backward liveness equations), skipping the top-level place
+/// written by a direct assignment, a call's destination or a yield's resume
+/// argument, which are writes, not reads.
+struct ReadCollector<'a, 'tcx> {
+  place_domain: &'a PlaceDomain<'tcx>,
+  reads: PlaceSet<'tcx>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ReadCollector<'a, 'tcx> {
+  fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _location: Location) {
+    let is_direct_assign_target = place.projection.is_empty()
+      && matches!(
+        context,
+        PlaceContext::MutatingUse(
+          MutatingUseContext::Store | MutatingUseContext::Call | MutatingUseContext::Yield
+        )
+      );
+    if context.is_use() && !is_direct_assign_target {
+      self.reads.insert(self.place_domain.index(place));
+    }
+  }
+}
+
+/// A standard backward live-variables dataflow: `gen` is the places read by
+/// a statement/terminator, `kill` is the place a direct (non-projected)
+/// `Assign` statement fully overwrites. A terminator kills nothing: the
+/// destination of a `Call` or the resume argument of a `Yield` keeps whatever
+/// liveness it has after the terminator. Used to tell [`DepVisitor`] which defining
+/// locations in a backward slice are dead, i.e. their value never reaches
+/// the queried target.
+struct LivenessAnalysis<'a, 'tcx> {
+  place_domain: &'a PlaceDomain<'tcx>,
+}
+
+impl<'a, 'tcx> AnalysisDomain<'tcx> for LivenessAnalysis<'a, 'tcx> {
+  type Direction = Backward;
+  type Domain = LivenessDomain<'tcx>;
+  const NAME: &'static str = "FlowistryLiveness";
+
+  fn bottom_value(&self, _body: &Body<'tcx>) -> Self::Domain {
+    LivenessDomain(PlaceSet::new(self.place_domain))
+  }
+
+  fn initialize_start_block(&self, _body: &Body<'tcx>, _state: &mut Self::Domain) {}
+}
+
+impl<'a, 'tcx> Analysis<'tcx> for LivenessAnalysis<'a, 'tcx> {
+  fn apply_statement_effect(
+    &self,
+    state: &mut Self::Domain,
+    statement: &Statement<'tcx>,
+    location: Location,
+  ) {
+    if let StatementKind::Assign(box (place, _)) = &statement.kind {
+      if place.projection.is_empty() {
+        state.0.remove(self.place_domain.index(place));
+      }
+    }
+
+    let mut collector = ReadCollector {
+      place_domain: self.place_domain,
+      reads: PlaceSet::new(self.place_domain),
+    };
+    collector.visit_statement(statement, location);
+    state.0.union(&collector.reads);
+  }
+
+  fn apply_terminator_effect(
+    &self,
+    state: &mut Self::Domain,
+    terminator: &Terminator<'tcx>,
+    location: Location,
+  ) {
+    let mut collector = ReadCollector {
+      place_domain: self.place_domain,
+      reads: PlaceSet::new(self.place_domain),
+    };
+    collector.visit_terminator(terminator, location);
+    state.0.union(&collector.reads);
+  }
+}
+
+/// Runs [`LivenessAnalysis`] and snapshots the live-out set (what's live
+/// immediately after the statement/terminator, i.e. before this location's
+/// own `kill`/`gen` is applied to walk further backward) at every location.
+/// For a backward analysis this is `seek_before_primary_effect`, not
+/// `seek_after_primary_effect` -- the latter yields live-*in*, which already
+/// has this location's own kill applied and so (almost) never contains the
+/// place this same location just defined.
+fn compute_liveness(
+  tcx: rustc_middle::ty::TyCtxt<'tcx>,
+  body: &'mir Body<'tcx>,
+  place_domain: &'mir PlaceDomain<'tcx>,
+) -> FxHashMap<Location, PlaceSet<'tcx>> {
+  let analysis = LivenessAnalysis { place_domain };
+  let mut cursor = analysis
+    .into_engine(tcx, body)
+    .iterate_to_fixpoint()
+    .into_results_cursor(body);
+
+  let mut live_out = FxHashMap::default();
+  for (block, data) in body.basic_blocks().iter_enumerated() {
+    for statement_index in 0 ..= data.statements.len() {
+      let location = Location { block, statement_index };
+      cursor.seek_before_primary_effect(location);
+      live_out.insert(location, cursor.get().0.clone());
+    }
+  }
+  live_out
+}
+
 struct DepVisitor<'a, 'mir, 'tcx> {
   direction: Direction,
   target_deps: Vec<LocationSet>,
   outputs: Vec<(LocationSet, PlaceSet<'tcx>)>,
   analysis: &'a FlowAnalysis<'mir, 'tcx>,
+  dead_blocks: FxHashSet<BasicBlock>,
+  /// Statically-dead `(block, successor)` edges, as found by
+  /// [`dead_switch_edges`]. Populated alongside `dead_blocks` (i.e. only when
+  /// [`SliceOptions::prune_dead_branches`] is set); used to recognize a
+  /// `SwitchInt` that const-prop has reduced to a single live successor, which
+  /// contributes no real control dependency.
+  dead_edges: FxHashSet<(BasicBlock, BasicBlock)>,
+  /// `Some` when [`SliceOptions::flow_sensitive_aliasing`] is set.
+  points_to: Option<FxHashMap<Location, PointsToState<'tcx>>>,
+  /// `Some` (even if empty) when [`SliceOptions::minimal_backward_slice`] is
+  /// set and the direction is `Backward` or `Both`, giving the live-out
+  /// [`PlaceSet`] at every location; `None` for `Forward`.
+  liveness: Option<FxHashMap<Location, PlaceSet<'tcx>>>,
 }
 
 impl DepVisitor<'_, '_, 'tcx> {
@@ -48,15 +532,38 @@ impl DepVisitor<'_, '_, 'tcx> {
         .map(|place| (place, state.row_set(place)))
         .filter(|(_, loc_deps)| !loc_deps.is_empty())
       {
+        let forward_matches = loc_deps.is_superset(target_locs);
+        let backward_matches = target_locs.is_superset(&loc_deps);
         let matches = match self.direction {
-          Direction::Forward => loc_deps.is_superset(target_locs),
-          Direction::Backward => target_locs.is_superset(&loc_deps),
-          Direction::Both => {
-            loc_deps.is_superset(target_locs) || target_locs.is_superset(&loc_deps)
-          }
+          Direction::Forward => forward_matches,
+          Direction::Backward => backward_matches,
+          Direction::Both => forward_matches || backward_matches,
         };
 
         if matches {
+          // The minimal-backward-slice pruning only applies to a match that
+          // is backward in nature; under `Both`, a place reached via the
+          // forward criterion must still be kept even if it isn't live.
+          let prune_for_liveness = match self.direction {
+            Direction::Backward => true,
+            Direction::Both => backward_matches && !forward_matches,
+            Direction::Forward => false,
+          };
+          if prune_for_liveness {
+            if let Some(live_out) = &self.liveness {
+              let live = opt_location
+                .map(|location| {
+                  live_out
+                    .get(&location)
+                    .map_or(true, |live_places| live_places.contains(place))
+                })
+                .unwrap_or(true);
+              if !live {
+                continue;
+              }
+            }
+          }
+
           trace!(
             "{opt_location:?}: place {:?} (deps {loc_deps:?}) / target_locs {target_locs:?}",
             state.row_domain.value(place)
@@ -74,6 +581,53 @@ impl DepVisitor<'_, '_, 'tcx> {
       }
     }
   }
+
+  /// Narrows `conflicts` (the static, flow-insensitive aliasing of a write
+  /// through `mutated`) down to the places the flow-sensitive points-to
+  /// analysis proves `mutated`'s pointer can actually reach at `location`.
+  /// Falls back to `conflicts` unchanged when `mutated` isn't a pointer
+  /// write, or when the points-to analysis has no positive facts for it at
+  /// `location` (no entry yet, or an empty target set -- e.g. a pointer of
+  /// unknown provenance from a cast or a `Call` destination, which
+  /// [`PointsToVisitor`] never tracks). An empty/unknown result must mean
+  /// "don't know", not "points nowhere", or a real write would silently
+  /// vanish from the slice.
+  fn refine_conflicts(
+    &self,
+    mutated: Place<'tcx>,
+    location: Location,
+    conflicts: PlaceSet<'tcx>,
+  ) -> PlaceSet<'tcx> {
+    if !mutated.is_indirect() {
+      return conflicts;
+    }
+
+    let targets = self
+      .points_to
+      .as_ref()
+      .and_then(|points_to| points_to.get(&location))
+      .map(|state| state.targets_of(Place::from(mutated.local)))
+      .unwrap_or_default();
+    if targets.is_empty() {
+      return conflicts;
+    }
+
+    let place_domain = self.analysis.place_domain();
+    let mut refined = PlaceSet::new(place_domain);
+    for place_idx in conflicts.indices() {
+      let place = place_domain.value(place_idx);
+      if targets.iter().any(|target| target.local == place.local) {
+        refined.insert(place_idx);
+      }
+    }
+    // A positive points-to fact that somehow matches none of the static
+    // conflicts means the two analyses disagree, not that the write has no
+    // targets -- keep the conservative set rather than dropping the write.
+    if refined.is_empty() {
+      return conflicts;
+    }
+    refined
+  }
 }
 
 impl ResultsVisitor<'mir, 'tcx> for DepVisitor<'_, 'mir, 'tcx> {
@@ -105,13 +659,18 @@ impl ResultsVisitor<'mir, 'tcx> for DepVisitor<'_, 'mir, 'tcx> {
     statement: &'mir Statement<'tcx>,
     location: Location,
   ) {
+    if self.dead_blocks.contains(&location.block) {
+      return;
+    }
+
     let mut to_check = PlaceSet::new(self.analysis.place_domain());
     ModularMutationVisitor::new(
       self.analysis.tcx,
       self.analysis.body,
       self.analysis.def_id,
       |mutated, _, _, _| {
-        to_check.union(&self.analysis.aliases.conflicts(mutated));
+        let conflicts = self.analysis.aliases.conflicts(mutated);
+        to_check.union(&self.refine_conflicts(mutated, location, conflicts));
       },
     )
     .visit_statement(statement, location);
@@ -124,8 +683,26 @@ impl ResultsVisitor<'mir, 'tcx> for DepVisitor<'_, 'mir, 'tcx> {
     terminator: &'mir rustc_middle::mir::Terminator<'tcx>,
     location: Location,
   ) {
+    if self.dead_blocks.contains(&location.block) {
+      return;
+    }
+
     match terminator.kind {
       TerminatorKind::SwitchInt { .. } => {
+        // If const-prop has pruned every successor but one, this switch no
+        // longer represents a real branch -- all paths through it take the
+        // same edge, so it contributes no control dependency. Skip it rather
+        // than conservatively tying every place in the domain to it.
+        let is_pruned = terminator
+          .successors()
+          .any(|successor| self.dead_edges.contains(&(location.block, successor)));
+        let live_successors = terminator
+          .successors()
+          .filter(|successor| !self.dead_edges.contains(&(location.block, *successor)))
+          .count();
+        if is_pruned && live_successors <= 1 {
+          return;
+        }
         let to_check = PlaceDomain::as_set(self.analysis.place_domain());
         self.visit(state, Some(location), to_check, true);
       }
@@ -136,7 +713,8 @@ impl ResultsVisitor<'mir, 'tcx> for DepVisitor<'_, 'mir, 'tcx> {
           self.analysis.body,
           self.analysis.def_id,
           |mutated, _, _, _| {
-            to_check.union(&self.analysis.aliases.conflicts(mutated));
+            let conflicts = self.analysis.aliases.conflicts(mutated);
+            to_check.union(&self.refine_conflicts(mutated, location, conflicts));
           },
         )
         .visit_terminator(terminator, location);
@@ -146,16 +724,34 @@ impl ResultsVisitor<'mir, 'tcx> for DepVisitor<'_, 'mir, 'tcx> {
   }
 }
 
+/// Computes the dependencies of `targets`.
+///
+/// See [`SliceOptions`] for the opt-in precision passes this supports; all
+/// of them default to off, preserving the conservative (over-approximate)
+/// behavior existing callers expect.
 pub fn compute_dependencies(
   results: &FlowResults<'_, 'tcx>,
   targets: Vec<(Place<'tcx>, Location)>,
   direction: Direction,
+  options: SliceOptions,
 ) -> Vec<(LocationSet, PlaceSet<'tcx>)> {
   block_timer!("compute_dependencies");
   let tcx = results.analysis.tcx;
   let body = results.analysis.body;
   let aliases = &results.analysis.aliases;
 
+  let (dead_blocks, dead_edges) = if options.prune_dead_branches {
+    let dead_edges = dead_switch_edges(tcx, body);
+    let dead_blocks = dead_switch_blocks(body, &dead_edges);
+    (dead_blocks, dead_edges)
+  } else {
+    (FxHashSet::default(), FxHashSet::default())
+  };
+  let points_to = options.flow_sensitive_aliasing.then(|| compute_points_to(body));
+  let liveness = (options.minimal_backward_slice
+    && matches!(direction, Direction::Backward | Direction::Both))
+  .then(|| compute_liveness(tcx, body, results.analysis.place_domain()));
+
   let new_location_set = || LocationSet::new(results.analysis.location_domain());
   let new_place_set = || PlaceSet::new(results.analysis.place_domain());
 
@@ -197,6 +793,10 @@ pub fn compute_dependencies(
     direction,
     target_deps,
     outputs,
+    dead_blocks,
+    dead_edges,
+    points_to,
+    liveness,
   };
   results.visit_reachable_with(body, &mut visitor);
   debug!("visitor.outputs: {:?}", visitor.outputs);
@@ -208,12 +808,13 @@ pub fn compute_dependency_spans(
   results: &FlowResults<'_, 'tcx>,
   targets: Vec<(Place<'tcx>, Location)>,
   direction: Direction,
+  options: SliceOptions,
   spanner: &Spanner,
 ) -> Vec<Vec<Span>> {
   let tcx = results.analysis.tcx;
   let body = results.analysis.body;
 
-  let deps = compute_dependencies(results, targets, direction);
+  let deps = compute_dependencies(results, targets, direction, options);
 
   deps
     .into_iter()
@@ -238,3 +839,102 @@ pub fn compute_dependency_spans(
     })
     .collect::<Vec<_>>()
 }
+
+/// Renders the output of [`compute_dependencies`] as a GraphViz DOT graph, one
+/// cluster per target, so a slice can be inspected visually instead of only
+/// through `trace!` output.
+///
+/// Nodes are emitted for every [`Location`] in a target's [`LocationSet`] and
+/// every [`Place`] in its [`PlaceSet`], labelled with their source spans via
+/// `spanner`. An edge `a -> b` is drawn from a location node `a` to a place
+/// node `b` when `b`'s location-deps (its row in the [`FlowDomain`] at the
+/// target's location) contain `a`, i.e. `a` is one of the program points that
+/// `b`'s value depends on. Only locations that are themselves nodes of the
+/// target's cluster get edges.
+pub fn compute_dependency_graph(
+  results: &FlowResults<'_, 'tcx>,
+  targets: Vec<(Place<'tcx>, Location)>,
+  direction: Direction,
+  options: SliceOptions,
+  spanner: &Spanner,
+) -> String {
+  let tcx = results.analysis.tcx;
+  let body = results.analysis.body;
+  let target_locations = targets.iter().map(|(_, location)| *location).collect::<Vec<_>>();
+
+  let deps = compute_dependencies(results, targets, direction, options);
+
+  let mut dot = String::new();
+  writeln!(dot, "digraph dependencies {{").unwrap();
+
+  for (i, (locations, places)) in deps.iter().enumerate() {
+    writeln!(dot, "  subgraph cluster_{i} {{").unwrap();
+    writeln!(dot, "    label = \"target {i}\";").unwrap();
+
+    for location in locations.iter() {
+      let label = location_label(*location, spanner);
+      writeln!(
+        dot,
+        "    \"loc_{i}_{location:?}\" [shape=box, label=\"{label}\"];"
+      )
+      .unwrap();
+    }
+
+    let state = results.state_at(target_locations[i]);
+    for place_idx in places.indices() {
+      let place = state.row_domain.value(place_idx);
+      let label = place_label(*place, tcx, body, spanner);
+      writeln!(
+        dot,
+        "    \"place_{i}_{place:?}\" [shape=ellipse, label=\"{label}\"];"
+      )
+      .unwrap();
+
+      for location in state.row_set(place_idx).iter() {
+        if !locations.contains(*location) {
+          continue;
+        }
+        writeln!(
+          dot,
+          "    \"loc_{i}_{location:?}\" -> \"place_{i}_{place:?}\";"
+        )
+        .unwrap();
+      }
+    }
+
+    writeln!(dot, "  }}").unwrap();
+  }
+
+  writeln!(dot, "}}").unwrap();
+  dot
+}
+
+fn location_label(location: Location, spanner: &Spanner) -> String {
+  let spans = spanner.location_to_spans(location, EnclosingHirSpans::OuterOnly);
+  let snippet = spans
+    .first()
+    .map(|span| format!("{span:?}"))
+    .unwrap_or_else(|| format!("{location:?}"));
+  escape_dot_label(&snippet)
+}
+
+fn place_label(
+  place: Place<'tcx>,
+  tcx: rustc_middle::ty::TyCtxt<'tcx>,
+  body: &Body<'tcx>,
+  spanner: &Spanner,
+) -> String {
+  let span = body.local_decls()[place.local]
+    .source_info
+    .span
+    .as_local(tcx)
+    .filter(|span| !spanner.invalid_span(*span));
+  let snippet = span
+    .map(|span| format!("{place:?} @ {span:?}"))
+    .unwrap_or_else(|| format!("{place:?}"));
+  escape_dot_label(&snippet)
+}
+
+fn escape_dot_label(s: &str) -> String {
+  s.replace('\\', "\\\\").replace('"', "\\\"")
+}